@@ -1,15 +1,16 @@
-use core::panic;
 use std::{
     env,
     io::{self, BufReader},
     path::{Path, PathBuf},
-    process::{Command, Stdio},
+    process::{Command, ExitStatus, Stdio},
 };
 
+use anyhow::{anyhow, bail, Context, Result};
 use cargo_metadata::{Artifact, Message, Package};
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use colored::Colorize;
 use either::Either;
+use serde::Serialize;
 use tee::TeeReader;
 use walkdir::WalkDir;
 
@@ -17,17 +18,152 @@ use crate::meta::{parse_crate_metadata, PackageMetadata, TitleId, VITA_TARGET};
 
 use super::Executor;
 
+mod net;
+
 #[derive(Args, Debug)]
 pub struct Build {
     #[command(subcommand)]
     cmd: BuildCmd,
 
+    /// Package to build. May be repeated to build multiple crates in a
+    /// workspace, each into its own artifact.
+    #[arg(long = "package", short = 'p', global = true)]
+    packages: Vec<String>,
+
+    /// Build every crate in the workspace.
+    #[arg(long, global = true)]
+    workspace: bool,
+
+    /// Output format for the produced artifacts.
+    #[arg(long, global = true, value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+
     #[arg(trailing_var_arg = true)]
     #[arg(allow_hyphen_values = true)]
     #[arg(global = true)]
     #[arg(name = "CARGO_ARGS")]
     args: Vec<String>,
 }
+
+impl Build {
+    /// Cargo arguments derived from `--package`/`--workspace`, followed by
+    /// any raw passthrough `CARGO_ARGS`.
+    fn cargo_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        for package in &self.packages {
+            args.push("--package".to_string());
+            args.push(package.clone());
+        }
+
+        if self.workspace {
+            args.push("--workspace".to_string());
+        }
+
+        args.extend(self.args.iter().cloned());
+        args
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum MessageFormat {
+    /// The default, colored, human-readable progress output.
+    Human,
+    /// One JSON object per produced artifact, newline-delimited.
+    Json,
+}
+
+/// Describes the files produced for one crate, printed as a single JSON
+/// line when `--message-format=json` is passed.
+#[derive(Serialize)]
+struct ArtifactMessage {
+    crate_name: String,
+    title_id: Option<String>,
+    velf: Option<PathBuf>,
+    eboot: Option<PathBuf>,
+    sfo: Option<PathBuf>,
+    vpk: Option<PathBuf>,
+}
+
+/// Absolute paths the pipeline writes its outputs to, derived from the
+/// artifact's own executable path.
+struct ArtifactPaths {
+    velf: PathBuf,
+    eboot: PathBuf,
+    sfo: PathBuf,
+    vpk: PathBuf,
+}
+
+fn artifact_paths(artifact: &Artifact) -> Result<ArtifactPaths> {
+    let elf = artifact
+        .executable
+        .as_deref()
+        .context("artifact has no executable")?;
+
+    let with_ext = |ext: &str| {
+        let mut path = PathBuf::from(elf);
+        path.set_extension(ext);
+        path
+    };
+
+    Ok(ArtifactPaths {
+        velf: with_ext("velf"),
+        eboot: with_ext("self"),
+        sfo: with_ext("sfo"),
+        vpk: with_ext("vpk"),
+    })
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    path.canonicalize()
+        .with_context(|| format!("failed to resolve the absolute path of {}", path.display()))
+}
+
+/// Resolves the title_id that will actually end up embedded in the
+/// artifact's `param.sfo`. `sfo()`, `run_on_vita()` and `test_on_vita()`
+/// must all agree on this, since the folder the app is deployed to and the
+/// id passed to `launch` have to match the id baked into the package
+/// itself.
+///
+/// Normally the Cargo.toml metadata value wins, falling back to
+/// `--default-title-id`. `cargo vita test` needs the opposite priority: its
+/// generated throwaway id must win over the crate's real `title_id`, or
+/// test runs would install over (and collide on) the production app.
+/// `prefer_default` flips the order for that case.
+fn resolve_title_id<'a>(
+    meta: &'a PackageMetadata,
+    args: &'a Sfo,
+    pkg: &Package,
+    prefer_default: bool,
+) -> Result<&'a TitleId> {
+    let (first, second) = if prefer_default {
+        (args.default_title_id.as_ref(), meta.title_id.as_ref())
+    } else {
+        (meta.title_id.as_ref(), args.default_title_id.as_ref())
+    };
+
+    first
+        .or(second)
+        .with_context(|| format!("title_id is not set for artifact {}", pkg.name))
+}
+
+/// Whether a title_id is available for this artifact, from either
+/// Cargo.toml metadata or `--default-title-id`. Artifacts without one are
+/// skipped rather than failing the whole command, since a workspace build
+/// may mix crates that produce a vpk with plain library/binary crates that
+/// don't.
+fn has_title_id(meta: &PackageMetadata, args: &Sfo) -> bool {
+    meta.title_id.is_some() || args.default_title_id.is_some()
+}
+
+fn print_artifact_message(format: MessageFormat, message: &ArtifactMessage) -> Result<()> {
+    if format == MessageFormat::Json {
+        println!("{}", serde_json::to_string(message)?);
+    }
+
+    Ok(())
+}
+
 #[derive(Subcommand, Debug)]
 #[command(allow_external_subcommands = true)]
 enum BuildCmd {
@@ -36,6 +172,8 @@ enum BuildCmd {
     Eboot,
     Sfo(Sfo),
     Vpk(Vpk),
+    Run(Run),
+    Test(Test),
 }
 
 #[derive(Args, Debug)]
@@ -51,67 +189,283 @@ struct Vpk {
     sfo: Sfo,
 }
 
+#[derive(Args, Debug)]
+struct Run {
+    #[command(flatten)]
+    vpk: Vpk,
+
+    /// IP address of the Vita to deploy to. Falls back to the `vita_ip`
+    /// Cargo.toml metadata key.
+    #[arg(long, env = "VITA_IP")]
+    vita_ip: Option<String>,
+
+    /// An argument forwarded to the launched app. May be repeated to pass
+    /// multiple arguments.
+    #[arg(long = "arg", allow_hyphen_values = true)]
+    run_args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct Test {
+    /// IP address of the Vita to deploy to. Falls back to the `vita_ip`
+    /// Cargo.toml metadata key.
+    #[arg(long, env = "VITA_IP")]
+    vita_ip: Option<String>,
+}
+
 impl Executor for Build {
-    fn execute(&self, verbose: u8) {
+    fn execute(&self, verbose: u8) -> Result<()> {
         let (meta, _) = parse_crate_metadata(None);
         let sdk = std::env::var("VITASDK");
         let sdk = meta
             .vita_sdk
             .as_deref()
             .or_else(|| sdk.as_deref().ok())
-            .unwrap_or_else(|| {
-                panic!(
+            .ok_or_else(|| {
+                anyhow!(
                     "VITASDK environment variable isn't set. Please install the SDK \
                     from https://vitasdk.org/ and set the VITASDK environment variable."
                 )
-            });
+            })?;
+
+        let cargo_args = self.cargo_args();
 
         match &self.cmd {
             BuildCmd::Elf => {
-                build_elf(&meta, sdk, &self.args, verbose);
+                build_elf(&meta, sdk, &cargo_args, verbose)?;
             }
             BuildCmd::Velf => {
-                for artifact in build_elf(&meta, sdk, &self.args, verbose) {
-                    let (meta, _) = parse_crate_metadata(Some(&artifact));
-
-                    strip(&artifact, sdk, &meta, verbose);
-                    velf(&artifact, sdk, &meta, verbose);
+                for artifact in build_elf(&meta, sdk, &cargo_args, verbose)? {
+                    let (meta, pkg) = parse_crate_metadata(Some(&artifact));
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    strip(&artifact, sdk, &meta, verbose)?;
+                    velf(&artifact, sdk, &meta, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: meta.title_id.as_ref().map(|id| id.0.clone()),
+                                velf: Some(absolute(&paths.velf)?),
+                                eboot: None,
+                                sfo: None,
+                                vpk: None,
+                            },
+                        )?;
+                    }
                 }
             }
             BuildCmd::Eboot => {
-                for artifact in build_elf(&meta, sdk, &self.args, verbose) {
-                    let (meta, _) = parse_crate_metadata(Some(&artifact));
-
-                    strip(&artifact, sdk, &meta, verbose);
-                    velf(&artifact, sdk, &meta, verbose);
-                    eboot(&artifact, sdk, &meta, verbose);
+                for artifact in build_elf(&meta, sdk, &cargo_args, verbose)? {
+                    let (meta, pkg) = parse_crate_metadata(Some(&artifact));
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    strip(&artifact, sdk, &meta, verbose)?;
+                    velf(&artifact, sdk, &meta, verbose)?;
+                    eboot(&artifact, sdk, &meta, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: meta.title_id.as_ref().map(|id| id.0.clone()),
+                                velf: Some(absolute(&paths.velf)?),
+                                eboot: Some(absolute(&paths.eboot)?),
+                                sfo: None,
+                                vpk: None,
+                            },
+                        )?;
+                    }
                 }
             }
             BuildCmd::Sfo(args) => {
-                for artifact in build_elf(&meta, sdk, &self.args, verbose) {
+                for artifact in build_elf(&meta, sdk, &cargo_args, verbose)? {
                     let (meta, pkg) = parse_crate_metadata(Some(&artifact));
-                    let pkg = pkg.expect("artifact does not have a package");
-
-                    sfo(&args, &artifact, sdk, &meta, &pkg, verbose);
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    if !has_title_id(&meta, args) {
+                        if verbose > 0 {
+                            println!(
+                                "{} {} (no title_id set in Cargo.toml metadata)",
+                                "Skipping:".yellow(),
+                                pkg.name
+                            );
+                        }
+                        continue;
+                    }
+
+                    sfo(args, &artifact, sdk, &meta, &pkg, false, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: Some(resolve_title_id(&meta, args, &pkg, false)?.0.clone()),
+                                velf: None,
+                                eboot: None,
+                                sfo: Some(absolute(&paths.sfo)?),
+                                vpk: None,
+                            },
+                        )?;
+                    }
                 }
             }
             BuildCmd::Vpk(args) => {
-                for artifact in build_elf(&meta, sdk, &self.args, verbose) {
+                for artifact in build_elf(&meta, sdk, &cargo_args, verbose)? {
                     let (meta, pkg) = parse_crate_metadata(Some(&artifact));
-                    let pkg = pkg.expect("artifact does not have a package");
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    if !has_title_id(&meta, &args.sfo) {
+                        if verbose > 0 {
+                            println!(
+                                "{} {} (no title_id set in Cargo.toml metadata)",
+                                "Skipping:".yellow(),
+                                pkg.name
+                            );
+                        }
+                        continue;
+                    }
+
+                    strip(&artifact, sdk, &meta, verbose)?;
+                    velf(&artifact, sdk, &meta, verbose)?;
+                    eboot(&artifact, sdk, &meta, verbose)?;
+                    sfo(&args.sfo, &artifact, sdk, &meta, &pkg, false, verbose)?;
+                    vpk(&artifact, sdk, &meta, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: Some(resolve_title_id(&meta, &args.sfo, &pkg, false)?.0.clone()),
+                                velf: Some(absolute(&paths.velf)?),
+                                eboot: Some(absolute(&paths.eboot)?),
+                                sfo: Some(absolute(&paths.sfo)?),
+                                vpk: Some(absolute(&paths.vpk)?),
+                            },
+                        )?;
+                    }
+                }
+            }
+            BuildCmd::Run(args) => {
+                for artifact in build_elf(&meta, sdk, &cargo_args, verbose)? {
+                    let (meta, pkg) = parse_crate_metadata(Some(&artifact));
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    if !has_title_id(&meta, &args.vpk.sfo) {
+                        if verbose > 0 {
+                            println!(
+                                "{} {} (no title_id set in Cargo.toml metadata)",
+                                "Skipping:".yellow(),
+                                pkg.name
+                            );
+                        }
+                        continue;
+                    }
+
+                    strip(&artifact, sdk, &meta, verbose)?;
+                    velf(&artifact, sdk, &meta, verbose)?;
+                    eboot(&artifact, sdk, &meta, verbose)?;
+                    sfo(&args.vpk.sfo, &artifact, sdk, &meta, &pkg, false, verbose)?;
+                    vpk(&artifact, sdk, &meta, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: Some(resolve_title_id(&meta, &args.vpk.sfo, &pkg, false)?.0.clone()),
+                                velf: Some(absolute(&paths.velf)?),
+                                eboot: Some(absolute(&paths.eboot)?),
+                                sfo: Some(absolute(&paths.sfo)?),
+                                vpk: Some(absolute(&paths.vpk)?),
+                            },
+                        )?;
+                    }
+
+                    run_on_vita(args, &artifact, &meta, &args.vpk.sfo, &pkg, verbose)?;
+                }
+            }
+            BuildCmd::Test(args) => {
+                let mut cargo_args = vec!["--tests".to_string()];
+                cargo_args.extend(self.cargo_args());
+
+                let mut all_passed = true;
+
+                for (i, artifact) in build_elf(&meta, sdk, &cargo_args, verbose)?.into_iter().enumerate() {
+                    let (meta, pkg) = parse_crate_metadata(Some(&artifact));
+                    let pkg = pkg.context("artifact does not have a package")?;
+
+                    let title_id: TitleId = format!("TEST{i:05}")
+                        .parse()
+                        .map_err(|_| anyhow!("generated throwaway title_id is invalid"))?;
+                    let sfo_args = Sfo {
+                        default_title_id: Some(title_id),
+                    };
+
+                    strip(&artifact, sdk, &meta, verbose)?;
+                    velf(&artifact, sdk, &meta, verbose)?;
+                    eboot(&artifact, sdk, &meta, verbose)?;
+                    sfo(&sfo_args, &artifact, sdk, &meta, &pkg, true, verbose)?;
+                    vpk(&artifact, sdk, &meta, verbose)?;
+
+                    if self.message_format == MessageFormat::Json {
+                        let paths = artifact_paths(&artifact)?;
+                        print_artifact_message(
+                            self.message_format,
+                            &ArtifactMessage {
+                                crate_name: pkg.name.clone(),
+                                title_id: Some(resolve_title_id(&meta, &sfo_args, &pkg, true)?.0.clone()),
+                                velf: Some(absolute(&paths.velf)?),
+                                eboot: Some(absolute(&paths.eboot)?),
+                                sfo: Some(absolute(&paths.sfo)?),
+                                vpk: Some(absolute(&paths.vpk)?),
+                            },
+                        )?;
+                    }
+
+                    all_passed &= test_on_vita(args, &artifact, &meta, &sfo_args, &pkg, verbose)?;
+                }
 
-                    strip(&artifact, sdk, &meta, verbose);
-                    velf(&artifact, sdk, &meta, verbose);
-                    eboot(&artifact, sdk, &meta, verbose);
-                    sfo(&args.sfo, &artifact, sdk, &meta, &pkg, verbose);
-                    vpk(&artifact, sdk, &meta, verbose);
+                if !all_passed {
+                    bail!("one or more on-device test binaries failed");
                 }
             }
         };
+
+        Ok(())
     }
 }
 
-fn build_elf(meta: &PackageMetadata, sdk: &str, args: &[String], verbose: u8) -> Vec<Artifact> {
+/// Checks a finished command's exit status, turning anything but a clean
+/// exit into an error carrying the command that produced it.
+fn check_status(command: &Command, status: ExitStatus) -> Result<()> {
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => bail!("{command:?} exited with code {code}"),
+        None => bail!("{command:?} terminated by signal"),
+    }
+}
+
+/// Runs `command` to completion and turns a non-zero exit code or signal
+/// termination into an error instead of silently continuing.
+fn run(command: &mut Command) -> Result<()> {
+    let status = command
+        .status()
+        .with_context(|| format!("failed to run {command:?}"))?;
+    check_status(command, status)
+}
+
+fn build_elf(meta: &PackageMetadata, sdk: &str, args: &[String], verbose: u8) -> Result<Vec<Artifact>> {
     let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
 
     let rust_flags = env::var("RUSTFLAGS").unwrap_or_default()
@@ -146,8 +500,10 @@ fn build_elf(meta: &PackageMetadata, sdk: &str, args: &[String], verbose: u8) ->
         println!("{} {command:?}", "Running cargo:".blue());
     }
 
-    let mut process = command.spawn().unwrap();
-    let command_stdout = process.stdout.take().unwrap();
+    let mut process = command
+        .spawn()
+        .with_context(|| format!("failed to run {command:?}"))?;
+    let command_stdout = process.stdout.take().expect("cargo's stdout was piped");
 
     let reader = if verbose > 1 {
         Either::Left(BufReader::new(TeeReader::new(command_stdout, io::stdout())))
@@ -157,19 +513,24 @@ fn build_elf(meta: &PackageMetadata, sdk: &str, args: &[String], verbose: u8) ->
 
     let messages: Vec<Message> = Message::parse_stream(reader)
         .collect::<io::Result<_>>()
-        .unwrap();
+        .context("failed to parse cargo's JSON message stream")?;
+
+    let status = process
+        .wait()
+        .with_context(|| format!("failed to run {command:?}"))?;
+    check_status(&command, status)?;
 
-    messages
+    Ok(messages
         .iter()
         .rev()
         .filter_map(|m| match m {
             Message::CompilerArtifact(art) if art.executable.is_some() => Some(art.clone()),
             _ => None,
         })
-        .collect()
+        .collect())
 }
 
-fn strip(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
+fn strip(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) -> Result<()> {
     let sdk = Path::new(sdk);
     let mut command = Command::new(sdk.join("bin").join("arm-vita-eabi-strip").as_os_str());
 
@@ -179,7 +540,7 @@ fn strip(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
             artifact
                 .executable
                 .as_deref()
-                .expect("Artifact has no executables"),
+                .context("artifact has no executable")?,
         )
         .stdout(Stdio::piped())
         .stdin(Stdio::inherit())
@@ -189,16 +550,16 @@ fn strip(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
         println!("{} {command:?}", "Stripping elf:".blue());
     }
 
-    command.status().expect("Artifact has no executables");
+    run(&mut command)
 }
 
-fn velf(artifact: &Artifact, sdk: &str, _meta: &PackageMetadata, verbose: u8) {
+fn velf(artifact: &Artifact, sdk: &str, _meta: &PackageMetadata, verbose: u8) -> Result<()> {
     let sdk = Path::new(sdk);
     let mut command = Command::new(sdk.join("bin").join("vita-elf-create").as_os_str());
     let elf = artifact
         .executable
         .as_deref()
-        .expect("Artifact has no executables");
+        .context("artifact has no executable")?;
 
     let mut velf = PathBuf::from(&elf);
     velf.set_extension("velf");
@@ -214,16 +575,16 @@ fn velf(artifact: &Artifact, sdk: &str, _meta: &PackageMetadata, verbose: u8) {
         println!("{} {command:?}", "Creating velf:".blue());
     }
 
-    command.status().expect("vita-elf-create failed");
+    run(&mut command)
 }
 
-fn eboot(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
+fn eboot(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) -> Result<()> {
     let sdk = Path::new(sdk);
     let mut command = Command::new(sdk.join("bin").join("vita-make-fself").as_os_str());
     let elf = artifact
         .executable
         .as_deref()
-        .expect("Artifact has no executables");
+        .context("artifact has no executable")?;
 
     let mut velf = PathBuf::from(&elf);
     velf.set_extension("velf");
@@ -243,7 +604,7 @@ fn eboot(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
         println!("{} {command:?}", "Creating eboot:".blue());
     }
 
-    command.status().expect("vita-make-fself failed");
+    run(&mut command)
 }
 
 fn sfo(
@@ -252,26 +613,22 @@ fn sfo(
     sdk: &str,
     meta: &PackageMetadata,
     pkg: &Package,
+    prefer_default_title_id: bool,
     verbose: u8,
-) {
+) -> Result<()> {
     let sdk = Path::new(sdk);
     let mut command = Command::new(sdk.join("bin").join("vita-mksfoex").as_os_str());
     let elf = artifact
         .executable
         .as_deref()
-        .expect("Artifact has no executables");
+        .context("artifact has no executable")?;
 
     let mut sfo = PathBuf::from(&elf);
     sfo.set_extension("sfo");
 
-    let title_name = meta.title_name.as_deref().unwrap_or_else(|| &pkg.name);
+    let title_name = meta.title_name.as_deref().unwrap_or(&pkg.name);
 
-    let title_id = &meta
-        .title_id
-        .as_ref()
-        .or(args.default_title_id.as_ref())
-        .expect(&format!("title_id is not set for artifact {}", pkg.name))
-        .0;
+    let title_id = &resolve_title_id(meta, args, pkg, prefer_default_title_id)?.0;
 
     command
         .args(&meta.vita_mksfoex_flags)
@@ -287,14 +644,14 @@ fn sfo(
         println!("{} {command:?}", "Creating sfo:".blue());
     }
 
-    command.status().expect("vita-mksfoex failed");
+    run(&mut command)
 }
 
-fn vpk(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
+fn vpk(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) -> Result<()> {
     let elf = artifact
         .executable
         .as_deref()
-        .expect("Artifact has no executables");
+        .context("artifact has no executable")?;
 
     let mut eboot = PathBuf::from(&elf);
     eboot.set_extension("self");
@@ -335,5 +692,94 @@ fn vpk(artifact: &Artifact, sdk: &str, meta: &PackageMetadata, verbose: u8) {
         println!("{} {command:?}", "Building vpk:".blue());
     }
 
-    command.status().expect("vita-mksfoex failed");
+    run(&mut command)
+}
+
+fn run_on_vita(
+    args: &Run,
+    artifact: &Artifact,
+    meta: &PackageMetadata,
+    sfo_args: &Sfo,
+    pkg: &Package,
+    verbose: u8,
+) -> Result<()> {
+    let elf = artifact
+        .executable
+        .as_deref()
+        .context("artifact has no executable")?;
+
+    let mut eboot = PathBuf::from(&elf);
+    eboot.set_extension("self");
+
+    let title_id = &resolve_title_id(meta, sfo_args, pkg, false)?.0;
+
+    let vita_ip = args
+        .vita_ip
+        .as_deref()
+        .or(meta.vita_ip.as_deref())
+        .context("Vita IP is not set, pass --vita-ip, set VITA_IP or the vita_ip Cargo.toml metadata key")?;
+
+    let remote_path = format!("ux0:/app/{title_id}/eboot.bin");
+
+    if verbose > 0 {
+        println!("{} {} to {vita_ip}:{}", "Deploying:".blue(), eboot.display(), net::FTP_PORT);
+    }
+
+    net::upload_file(vita_ip, &eboot, &remote_path, verbose).context("failed to upload eboot over FTP")?;
+
+    if verbose > 0 {
+        println!("{} {title_id} on {vita_ip}", "Launching:".blue());
+    }
+
+    net::launch_title(vita_ip, title_id, &args.run_args, verbose).context("failed to trigger launch")
+}
+
+fn test_on_vita(
+    args: &Test,
+    artifact: &Artifact,
+    meta: &PackageMetadata,
+    sfo_args: &Sfo,
+    pkg: &Package,
+    verbose: u8,
+) -> Result<bool> {
+    let elf = artifact
+        .executable
+        .as_deref()
+        .context("artifact has no executable")?;
+
+    let mut eboot = PathBuf::from(&elf);
+    eboot.set_extension("self");
+
+    // Always prefer the generated throwaway id over `meta.title_id`: a test
+    // binary must never be deployed under the crate's real production
+    // title_id, or it would collide with (and potentially clobber) the
+    // installed app. `cargo vita test` always passes a generated id here (it
+    // fails outright if one cannot be made), so the `meta.title_id` fallback
+    // is not reached from that path.
+    let title_id = &resolve_title_id(meta, sfo_args, pkg, true)?.0;
+
+    let vita_ip = args
+        .vita_ip
+        .as_deref()
+        .or(meta.vita_ip.as_deref())
+        .context("Vita IP is not set, pass --vita-ip, set VITA_IP or the vita_ip Cargo.toml metadata key")?;
+
+    let remote_path = format!("ux0:/app/{title_id}/eboot.bin");
+
+    if verbose > 0 {
+        println!("{} {} ({})", "Testing:".blue(), pkg.name, title_id);
+    }
+
+    net::upload_file(vita_ip, &eboot, &remote_path, verbose).context("failed to upload test eboot over FTP")?;
+
+    let local_ip =
+        net::local_ip_for(vita_ip).context("failed to determine a local address reachable from the Vita")?;
+    let stdout_addr = format!("{local_ip}:{}", net::TEST_RESULT_PORT);
+    let listener = net::bind_test_listener(net::TEST_RESULT_PORT).context("failed to listen for test output")?;
+
+    net::launch_title(vita_ip, title_id, &[stdout_addr], verbose).context("failed to trigger launch")?;
+
+    let stream = net::accept_test_output(&listener, net::TEST_OUTPUT_TIMEOUT, verbose)
+        .context("failed to receive test output")?;
+    net::read_test_result(stream, verbose).context("failed to read test output")
 }
@@ -0,0 +1,222 @@
+//! Minimal networking helpers for getting a built eboot onto a Vita and
+//! launching it, talking directly to VitaShell's FTP server (port 1337) and
+//! the companion debug server (port 1338) over raw sockets.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, UdpSocket},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use colored::Colorize;
+
+/// Default port VitaShell's built-in FTP server listens on.
+pub const FTP_PORT: u16 = 1337;
+/// Default port the companion debug server listens on for launch requests.
+pub const LAUNCH_PORT: u16 = 1338;
+/// Port `cargo vita test` listens on for a deployed test binary to stream
+/// its libtest output back to.
+pub const TEST_RESULT_PORT: u16 = 1339;
+/// How long to wait for the deployed test binary to connect back before
+/// giving up, so a binary that panics or hangs before reaching `main`
+/// doesn't wedge `cargo vita test` forever.
+pub const TEST_OUTPUT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Uploads `local_path` to `remote_path` (an absolute `ux0:`-style path) on
+/// the Vita at `vita_ip`, using a handful of raw FTP commands against
+/// VitaShell's FTP server.
+pub fn upload_file(vita_ip: &str, local_path: &Path, remote_path: &str, verbose: u8) -> io::Result<()> {
+    let addr = (vita_ip, FTP_PORT);
+    let mut control = BufReader::new(TcpStream::connect(addr)?);
+    expect_reply(&mut control, verbose)?;
+
+    send_command(control.get_mut(), "TYPE I", verbose)?;
+    expect_reply(&mut control, verbose)?;
+
+    if let Some(parent) = parent_dir(remote_path) {
+        // Best-effort: the directory may already exist.
+        send_command(control.get_mut(), &format!("MKD {parent}"), verbose)?;
+        let _ = read_reply(&mut control, verbose)?;
+    }
+
+    send_command(control.get_mut(), "PASV", verbose)?;
+    let pasv_reply = expect_reply(&mut control, verbose)?;
+    let data_addr = parse_pasv(&pasv_reply)?;
+
+    send_command(control.get_mut(), &format!("STOR {remote_path}"), verbose)?;
+    // STOR is acknowledged with a 1xx preliminary reply before the transfer;
+    // the final 2xx reply is read once the data connection is closed.
+    let stor_reply = read_reply(&mut control, verbose)?;
+    if !stor_reply.starts_with('1') {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("FTP command failed: {stor_reply:?}"),
+        ));
+    }
+
+    let mut data = TcpStream::connect(data_addr)?;
+    let mut file = std::fs::File::open(local_path)?;
+    io::copy(&mut file, &mut data)?;
+    drop(data);
+
+    expect_reply(&mut control, verbose)?;
+    send_command(control.get_mut(), "QUIT", verbose)?;
+
+    Ok(())
+}
+
+/// Opens a TCP connection to the companion debug server running on the Vita
+/// and asks it to launch the given title, optionally passing along extra
+/// arguments for the launched app.
+pub fn launch_title(vita_ip: &str, title_id: &str, args: &[String], verbose: u8) -> io::Result<()> {
+    let addr = (vita_ip, LAUNCH_PORT);
+    let mut stream = TcpStream::connect(addr)?;
+
+    let command = if args.is_empty() {
+        format!("launch {title_id}\n")
+    } else {
+        format!("launch {title_id} {}\n", args.join(" "))
+    };
+
+    if verbose > 0 {
+        println!("{} {command:?} to {vita_ip}:{LAUNCH_PORT}", "Sending:".blue());
+    }
+
+    stream.write_all(command.as_bytes())
+}
+
+fn send_command(stream: &mut TcpStream, command: &str, verbose: u8) -> io::Result<()> {
+    if verbose > 0 {
+        println!("{} {command}", "FTP >".blue());
+    }
+    stream.write_all(command.as_bytes())?;
+    stream.write_all(b"\r\n")
+}
+
+fn read_reply(control: &mut BufReader<TcpStream>, verbose: u8) -> io::Result<String> {
+    let mut line = String::new();
+    control.read_line(&mut line)?;
+
+    if verbose > 0 {
+        print!("{} {line}", "FTP <".blue());
+    }
+
+    Ok(line)
+}
+
+/// Reads a reply line and turns anything but a 2xx/3xx status code into an
+/// error, so a rejected command (e.g. a failed `STOR`) is reported instead
+/// of silently treated as success.
+fn expect_reply(control: &mut BufReader<TcpStream>, verbose: u8) -> io::Result<String> {
+    let reply = read_reply(control, verbose)?;
+
+    match reply.get(0..3).and_then(|code| code.parse::<u32>().ok()) {
+        Some(code) if (200..400).contains(&code) => Ok(reply),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("FTP command failed: {reply:?}"),
+        )),
+    }
+}
+
+fn parent_dir(remote_path: &str) -> Option<&str> {
+    let trimmed = remote_path.trim_end_matches('/');
+    trimmed.rfind('/').map(|i| &trimmed[..i])
+}
+
+/// Parses the `(h1,h2,h3,h4,p1,p2)` tuple out of a `227 Entering Passive
+/// Mode (...)` reply into a connectable `(host, port)` pair.
+fn parse_pasv(reply: &str) -> io::Result<(String, u16)> {
+    let start = reply
+        .find('(')
+        .ok_or_else(|| invalid_pasv_reply(reply))?;
+    let end = reply
+        .find(')')
+        .ok_or_else(|| invalid_pasv_reply(reply))?;
+
+    let numbers: Vec<u16> = reply[start + 1..end]
+        .split(',')
+        .map(|n| n.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid_pasv_reply(reply))?;
+
+    let [h1, h2, h3, h4, p1, p2] = numbers[..] else {
+        return Err(invalid_pasv_reply(reply));
+    };
+
+    Ok((format!("{h1}.{h2}.{h3}.{h4}"), (p1 << 8) | p2))
+}
+
+fn invalid_pasv_reply(reply: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected PASV reply: {reply:?}"),
+    )
+}
+
+/// Finds the local IP address this machine would use to reach `vita_ip`, so
+/// the deployed test binary can be told where to stream its output back to.
+pub fn local_ip_for(vita_ip: &str) -> io::Result<String> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect((vita_ip, FTP_PORT))?;
+    Ok(socket.local_addr()?.ip().to_string())
+}
+
+/// Starts listening on `port` for a deployed test binary to connect back
+/// and stream its libtest output. Must be bound before the title is
+/// launched, since the binary may connect as soon as it starts.
+pub fn bind_test_listener(port: u16) -> io::Result<TcpListener> {
+    TcpListener::bind(("0.0.0.0", port))
+}
+
+/// Waits for the launched test binary to connect to `listener`, giving up
+/// with a `TimedOut` error after `timeout` in case it crashed or hung
+/// before connecting back.
+pub fn accept_test_output(listener: &TcpListener, timeout: Duration, verbose: u8) -> io::Result<TcpStream> {
+    if verbose > 0 {
+        println!("{} on port {}", "Waiting for test output:".blue(), listener.local_addr()?.port());
+    }
+
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no test output received within {timeout:?}"),
+                    ));
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reads lines from a deployed libtest binary's stdout, forwarded back over
+/// `stream`, until the harness prints its `test result: ...` summary line.
+pub fn read_test_result(stream: TcpStream, verbose: u8) -> io::Result<bool> {
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+
+        if verbose > 0 || !line.is_empty() {
+            println!("{line}");
+        }
+
+        if let Some(summary) = line.strip_prefix("test result: ") {
+            return Ok(summary.starts_with("ok"));
+        }
+    }
+
+    Ok(false)
+}